@@ -1,8 +1,45 @@
+use semver::Version;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_store::{Store, StoreBuilder, StoreExt};
+
+const CONFIG_STORE: &str = "config.json";
+const CONFIG_KEY: &str = "config";
+const INSTALLED_STORE: &str = "installed.json";
+const INSTALLED_KEY: &str = "ids";
+const PACKS_STORE: &str = "packs.json";
+const MIGRATED_KEY: &str = "__migrated_from_fs";
+const REGISTRY_STORE: &str = "registry.json";
+const REGISTRY_CACHE_KEY: &str = "index";
+const REGISTRY_FETCHED_AT_KEY: &str = "fetched_at";
+const REGISTRY_TTL_SECONDS: i64 = 15 * 60;
+const CAPABILITIES_STORE: &str = "capabilities.json";
+/// How long a store waits after the last write before flushing to disk.
+/// Keeps rapid-fire edits (e.g. a rule being typed character by character)
+/// from each triggering their own full synchronous file write.
+const STORE_AUTO_SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Permissions a pack may request and the user may grant. A permission
+/// outside this list can never be granted. Of these, only `prompt:override`
+/// and `memo:write` are enforced (by `strip_ungranted_content` on export);
+/// `channels:read` and `external:fetch` are recorded but not checked.
+const KNOWN_PERMISSIONS: [&str; 4] = [
+    "memo:write",
+    "prompt:override",
+    "channels:read",
+    "external:fetch",
+];
+
+fn default_registry_base_url() -> String {
+    "https://registry.memomarket.dev".to_string()
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 struct Config {
@@ -13,6 +50,41 @@ struct Config {
     reasoning_enabled: bool,
     #[serde(default)]
     channels_json: String,
+    #[serde(default = "default_registry_base_url")]
+    registry_base_url: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            api_key: String::new(),
+            model_id: String::new(),
+            base_url: String::new(),
+            reasoning_enabled: false,
+            channels_json: String::new(),
+            registry_base_url: default_registry_base_url(),
+        }
+    }
+}
+
+/// One entry in a registry's `index.json`: enough metadata to list/search a
+/// pack plus what's needed to download and verify it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RegistryEntry {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    pub version: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub download_url: String,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct RegistryIndex {
+    packs: Vec<RegistryEntry>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -44,59 +116,473 @@ pub struct RulePack {
     pub created_at: String,
     #[serde(default)]
     pub updated_at: String,
+    /// Capabilities this pack's `system_prompt`/`rules` rely on, e.g.
+    /// `memo:write`, `prompt:override`, `channels:read`, `external:fetch`.
+    #[serde(default)]
+    pub permissions: Vec<String>,
 }
 
-fn get_config_path(app: &AppHandle) -> PathBuf {
+// --- legacy (pre tauri-plugin-store) file paths, used only by `migrate_legacy_storage` ---
+
+fn legacy_config_path(app: &AppHandle) -> PathBuf {
     let config_dir = app
         .path()
         .app_config_dir()
         .expect("failed to get config dir");
-    fs::create_dir_all(&config_dir).ok();
     config_dir.join("config.json")
 }
 
-fn get_packs_dir(app: &AppHandle) -> PathBuf {
+fn legacy_packs_dir(app: &AppHandle) -> PathBuf {
     let config_dir = app
         .path()
         .app_config_dir()
         .expect("failed to get config dir");
-    let packs = config_dir.join("packs");
-    fs::create_dir_all(&packs).ok();
-    packs
+    config_dir.join("packs")
 }
 
-fn get_installed_path(app: &AppHandle) -> PathBuf {
+fn legacy_installed_path(app: &AppHandle) -> PathBuf {
     let config_dir = app
         .path()
         .app_config_dir()
         .expect("failed to get config dir");
-    fs::create_dir_all(&config_dir).ok();
     config_dir.join("installed.json")
 }
 
+// --- tauri-plugin-store accessors ---
+
+fn config_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
+    app.store(CONFIG_STORE).map_err(|e| e.to_string())
+}
+
+fn installed_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
+    app.store(INSTALLED_STORE).map_err(|e| e.to_string())
+}
+
+fn packs_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
+    app.store(PACKS_STORE).map_err(|e| e.to_string())
+}
+
+fn baseline_key(id: &str) -> String {
+    format!("{}.base", id)
+}
+
+fn registry_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
+    app.store(REGISTRY_STORE).map_err(|e| e.to_string())
+}
+
+fn capabilities_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
+    app.store(CAPABILITIES_STORE).map_err(|e| e.to_string())
+}
+
+fn granted_capabilities(app: &AppHandle, pack_id: &str) -> Vec<String> {
+    capabilities_store(app)
+        .ok()
+        .and_then(|store| store.get(pack_id))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn is_capability_granted(app: &AppHandle, pack_id: &str, permission: &str) -> bool {
+    granted_capabilities(app, pack_id)
+        .iter()
+        .any(|granted| granted == permission)
+}
+
+/// Reset a pack's capability grants to "nothing granted". Used when a pack
+/// is freshly imported so a downloaded pack can never silently inherit
+/// grants from a previous pack that happened to reuse the same id.
+fn reset_pack_capabilities(app: &AppHandle, pack_id: &str) {
+    if let Ok(store) = capabilities_store(app) {
+        store.delete(pack_id);
+    }
+}
+
+/// One-time import of data written by the pre-store version of the app
+/// (raw `config.json` / `installed.json` / `packs/*.json`) into the
+/// corresponding tauri-plugin-store stores. No-op once it has run.
+///
+/// Unlike the per-command paths below, this forces an immediate `save()`
+/// rather than leaning on auto-save: it runs once at startup, not on every
+/// keystroke, and a crash before the first debounced flush shouldn't be
+/// able to make the migration silently re-run against a half-imported store.
+fn migrate_legacy_storage(app: &AppHandle) {
+    let Ok(installed) = installed_store(app) else {
+        return;
+    };
+    if installed.get(MIGRATED_KEY).is_some() {
+        return;
+    }
+
+    if let Ok(json) = fs::read_to_string(legacy_config_path(app)) {
+        if let Ok(value) = serde_json::from_str::<Value>(&json) {
+            if let Ok(config) = config_store(app) {
+                config.set(CONFIG_KEY, value);
+                config.save().ok();
+            }
+        }
+    }
+
+    if let Ok(json) = fs::read_to_string(legacy_installed_path(app)) {
+        if let Ok(value) = serde_json::from_str::<Value>(&json) {
+            installed.set(INSTALLED_KEY, value);
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(legacy_packs_dir(app)) {
+        if let Ok(packs) = packs_store(app) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().map_or(false, |e| e == "json") {
+                    if let (Ok(json), Some(stem)) = (
+                        fs::read_to_string(&path),
+                        path.file_stem().and_then(|s| s.to_str()),
+                    ) {
+                        if let Ok(value) = serde_json::from_str::<Value>(&json) {
+                            packs.set(stem.to_string(), value);
+                        }
+                    }
+                }
+            }
+            packs.save().ok();
+        }
+    }
+
+    installed.set(MIGRATED_KEY, serde_json::json!(true));
+    installed.save().ok();
+}
+
+fn read_pack_by_id(app: &AppHandle, id: &str) -> Option<RulePack> {
+    let store = packs_store(app).ok()?;
+    let value = store.get(id)?;
+    serde_json::from_value(value).ok()
+}
+
+fn read_baseline_by_id(app: &AppHandle, id: &str) -> Option<RulePack> {
+    let store = packs_store(app).ok()?;
+    let value = store.get(baseline_key(id))?;
+    serde_json::from_value(value).ok()
+}
+
+fn write_baseline(app: &AppHandle, pack: &RulePack) -> Result<(), String> {
+    let store = packs_store(app)?;
+    let value = serde_json::to_value(pack).map_err(|e| e.to_string())?;
+    store.set(baseline_key(&pack.id), value);
+    Ok(())
+}
+
+fn parse_pack_version(version: &str) -> Result<Version, String> {
+    Version::parse(version).map_err(|e| format!("invalid version '{}': {}", version, e))
+}
+
+/// Report returned by `upgrade_pack` describing how an incoming pack version
+/// was merged into the locally installed one.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UpgradeReport {
+    pub id: String,
+    pub from_version: String,
+    pub to_version: String,
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub conflicts: Vec<String>,
+}
+
+/// Outcome of importing a pack: either a brand new pack ready to be saved,
+/// or an in-place upgrade that has already been merged and persisted.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "kind")]
+pub enum ImportOutcome {
+    New { pack: RulePack },
+    Upgraded { report: UpgradeReport },
+}
+
+/// Result of checking a pack's requested `permissions` against what the
+/// user has granted it so far.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct CapabilityDecision {
+    pub granted: Vec<String>,
+    pub denied: Vec<String>,
+    pub requires_prompt: Vec<String>,
+}
+
+/// Three-way merge of `MemoRule`s keyed by title: the pristine baseline that
+/// was last shipped, the (possibly user-edited) installed copy, and the new
+/// incoming version. Returns the merged rule list plus a report of what
+/// happened to each title.
+fn merge_rules(
+    baseline: Option<&RulePack>,
+    installed: &RulePack,
+    incoming: &RulePack,
+) -> (Vec<MemoRule>, Vec<String>, Vec<String>, Vec<String>) {
+    let mut merged: Vec<MemoRule> = installed.rules.clone();
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for incoming_rule in &incoming.rules {
+        let installed_rule = installed
+            .rules
+            .iter()
+            .find(|r| r.title == incoming_rule.title);
+
+        match installed_rule {
+            None => {
+                let previously_shipped = baseline
+                    .map(|b| b.rules.iter().any(|r| r.title == incoming_rule.title))
+                    .unwrap_or(false);
+                if !previously_shipped {
+                    merged.push(incoming_rule.clone());
+                    added.push(incoming_rule.title.clone());
+                }
+                // Else: the rule existed in the baseline but the user has
+                // since removed it from `installed` — that's an intentional
+                // deletion, not a gap to backfill, so leave it out of
+                // `merged` even though `incoming` still ships it.
+            }
+            Some(installed_rule) => {
+                let baseline_rule = baseline
+                    .and_then(|b| b.rules.iter().find(|r| r.title == incoming_rule.title));
+
+                let unedited = match baseline_rule {
+                    Some(baseline_rule) => installed_rule.update_rule == baseline_rule.update_rule,
+                    // No baseline to compare against: treat as unedited so the
+                    // upgrade can still proceed instead of flagging everything.
+                    None => true,
+                };
+
+                if installed_rule.update_rule == incoming_rule.update_rule {
+                    // Nothing actually changed for this rule.
+                } else if unedited {
+                    if let Some(slot) = merged.iter_mut().find(|r| r.title == incoming_rule.title) {
+                        *slot = incoming_rule.clone();
+                    }
+                    updated.push(incoming_rule.title.clone());
+                } else {
+                    conflicts.push(incoming_rule.title.clone());
+                }
+            }
+        }
+    }
+
+    (merged, added, updated, conflicts)
+}
+
+#[cfg(test)]
+mod merge_rules_tests {
+    use super::*;
+
+    fn rule(title: &str, update_rule: &str) -> MemoRule {
+        MemoRule {
+            title: title.to_string(),
+            update_rule: update_rule.to_string(),
+        }
+    }
+
+    fn pack(rules: Vec<MemoRule>) -> RulePack {
+        RulePack {
+            id: "test-pack".to_string(),
+            name: "Test Pack".to_string(),
+            description: String::new(),
+            author: String::new(),
+            version: "1.0.0".to_string(),
+            system_prompt: String::new(),
+            rules,
+            memos: Vec::new(),
+            tags: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            permissions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn adds_a_rule_new_to_both_installed_and_baseline() {
+        let baseline = pack(vec![rule("a", "old a")]);
+        let installed = pack(vec![rule("a", "old a")]);
+        let incoming = pack(vec![rule("a", "old a"), rule("b", "new b")]);
+
+        let (merged, added, updated, conflicts) =
+            merge_rules(Some(&baseline), &installed, &incoming);
+
+        assert_eq!(added, vec!["b".to_string()]);
+        assert!(updated.is_empty());
+        assert!(conflicts.is_empty());
+        assert!(merged.iter().any(|r| r.title == "b"));
+    }
+
+    #[test]
+    fn does_not_resurrect_a_rule_the_user_deleted_locally() {
+        // "a" shipped in the baseline, the user removed it from their
+        // installed copy, and incoming still ships it unchanged.
+        let baseline = pack(vec![rule("a", "shipped a")]);
+        let installed = pack(vec![]);
+        let incoming = pack(vec![rule("a", "shipped a")]);
+
+        let (merged, added, updated, conflicts) =
+            merge_rules(Some(&baseline), &installed, &incoming);
+
+        assert!(added.is_empty(), "deleted rule should not be re-added");
+        assert!(updated.is_empty());
+        assert!(conflicts.is_empty());
+        assert!(!merged.iter().any(|r| r.title == "a"));
+    }
+
+    #[test]
+    fn silently_updates_a_rule_unedited_since_baseline() {
+        let baseline = pack(vec![rule("a", "old a")]);
+        let installed = pack(vec![rule("a", "old a")]);
+        let incoming = pack(vec![rule("a", "new a")]);
+
+        let (merged, added, updated, conflicts) =
+            merge_rules(Some(&baseline), &installed, &incoming);
+
+        assert!(added.is_empty());
+        assert_eq!(updated, vec!["a".to_string()]);
+        assert!(conflicts.is_empty());
+        assert_eq!(
+            merged.iter().find(|r| r.title == "a").unwrap().update_rule,
+            "new a"
+        );
+    }
+
+    #[test]
+    fn flags_a_conflict_when_the_user_edited_a_rule_upstream_also_changed() {
+        let baseline = pack(vec![rule("a", "old a")]);
+        let installed = pack(vec![rule("a", "user-edited a")]);
+        let incoming = pack(vec![rule("a", "new a")]);
+
+        let (merged, added, updated, conflicts) =
+            merge_rules(Some(&baseline), &installed, &incoming);
+
+        assert!(added.is_empty());
+        assert!(updated.is_empty());
+        assert_eq!(conflicts, vec!["a".to_string()]);
+        // The user's edit is left in place until they resolve the conflict.
+        assert_eq!(
+            merged.iter().find(|r| r.title == "a").unwrap().update_rule,
+            "user-edited a"
+        );
+    }
+
+    #[test]
+    fn leaves_a_rule_untouched_when_incoming_matches_installed() {
+        let baseline = pack(vec![rule("a", "old a")]);
+        let installed = pack(vec![rule("a", "user-edited a")]);
+        let incoming = pack(vec![rule("a", "user-edited a")]);
+
+        let (merged, added, updated, conflicts) =
+            merge_rules(Some(&baseline), &installed, &incoming);
+
+        assert!(added.is_empty());
+        assert!(updated.is_empty());
+        assert!(conflicts.is_empty());
+        assert_eq!(
+            merged.iter().find(|r| r.title == "a").unwrap().update_rule,
+            "user-edited a"
+        );
+    }
+
+    #[test]
+    fn treats_as_unedited_when_no_baseline_is_available() {
+        // No baseline on disk (e.g. a pack installed before upgrades existed):
+        // an upgrade should still proceed instead of flagging everything as
+        // a conflict.
+        let installed = pack(vec![rule("a", "old a")]);
+        let incoming = pack(vec![rule("a", "new a")]);
+
+        let (merged, added, updated, conflicts) = merge_rules(None, &installed, &incoming);
+
+        assert!(added.is_empty());
+        assert_eq!(updated, vec!["a".to_string()]);
+        assert!(conflicts.is_empty());
+        assert_eq!(
+            merged.iter().find(|r| r.title == "a").unwrap().update_rule,
+            "new a"
+        );
+    }
+
+    #[test]
+    fn adds_a_rule_with_no_baseline_at_all() {
+        // No baseline available: can't tell a deletion from a gap, so default
+        // to treating a title absent from `installed` as new.
+        let installed = pack(vec![]);
+        let incoming = pack(vec![rule("a", "new a")]);
+
+        let (merged, added, _updated, conflicts) = merge_rules(None, &installed, &incoming);
+
+        assert_eq!(added, vec!["a".to_string()]);
+        assert!(conflicts.is_empty());
+        assert!(merged.iter().any(|r| r.title == "a"));
+    }
+}
+
 #[tauri::command]
-fn load_config(app: AppHandle) -> Config {
-    let path = get_config_path(&app);
-    if path.exists() {
-        let json = fs::read_to_string(&path).unwrap_or_default();
-        serde_json::from_str(&json).unwrap_or(Config {
-            api_key: String::new(),
-            model_id: String::new(),
-            base_url: String::new(),
-            reasoning_enabled: false,
-            channels_json: String::new(),
-        })
-    } else {
-        Config {
-            api_key: String::new(),
-            model_id: String::new(),
-            base_url: String::new(),
-            reasoning_enabled: false,
-            channels_json: String::new(),
+fn upgrade_pack(app: AppHandle, incoming: RulePack) -> Result<UpgradeReport, String> {
+    let installed = read_pack_by_id(&app, &incoming.id)
+        .ok_or_else(|| format!("no installed pack with id '{}' to upgrade", incoming.id))?;
+
+    let installed_version = parse_pack_version(&installed.version)?;
+    let incoming_version = parse_pack_version(&incoming.version)?;
+
+    if incoming_version <= installed_version {
+        return Err(format!(
+            "refusing to downgrade pack '{}' from {} to {}",
+            incoming.id, installed.version, incoming.version
+        ));
+    }
+
+    let baseline = read_baseline_by_id(&app, &incoming.id);
+    let (merged_rules, added, updated, conflicts) =
+        merge_rules(baseline.as_ref(), &installed, &incoming);
+
+    let mut merged = incoming.clone();
+    merged.rules = merged_rules;
+
+    if !save_pack(app.clone(), merged) {
+        return Err(format!("failed to write upgraded pack '{}'", incoming.id));
+    }
+
+    write_baseline(&app, &incoming)?;
+
+    Ok(UpgradeReport {
+        id: incoming.id,
+        from_version: installed.version,
+        to_version: incoming.version,
+        added,
+        updated,
+        conflicts,
+    })
+}
+
+/// Resolve whether importing `incoming` should produce a brand new pack or
+/// an upgrade of one already installed under the same id.
+fn resolve_import(app: &AppHandle, incoming: RulePack) -> Result<ImportOutcome, String> {
+    match read_pack_by_id(app, &incoming.id) {
+        None => {
+            // A freshly imported pack cannot silently inherit permission
+            // grants from a previous pack that reused this id.
+            reset_pack_capabilities(app, &incoming.id);
+            // Capture the pristine shipped copy as the baseline now, so the
+            // *next* upgrade can tell a user edit apart from an upstream
+            // change instead of treating everything as unedited.
+            write_baseline(app, &incoming)?;
+            Ok(ImportOutcome::New { pack: incoming })
+        }
+        Some(_) => {
+            let report = upgrade_pack(app.clone(), incoming)?;
+            Ok(ImportOutcome::Upgraded { report })
         }
     }
 }
 
+#[tauri::command]
+fn load_config(app: AppHandle) -> Config {
+    config_store(&app)
+        .ok()
+        .and_then(|store| store.get(CONFIG_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
 #[tauri::command]
 fn save_config(
     app: AppHandle,
@@ -105,55 +591,92 @@ fn save_config(
     base_url: String,
     reasoning_enabled: bool,
     channels_json: String,
+    registry_base_url: Option<String>,
 ) -> bool {
-    let path = get_config_path(&app);
+    let Ok(store) = config_store(&app) else {
+        return false;
+    };
+    // Older frontend builds don't send `registry_base_url` yet; fall back to
+    // whatever is already stored (or the default) instead of requiring it.
+    let registry_base_url = registry_base_url.unwrap_or_else(|| {
+        store
+            .get(CONFIG_KEY)
+            .and_then(|value| serde_json::from_value::<Config>(value).ok())
+            .map(|config| config.registry_base_url)
+            .unwrap_or_else(default_registry_base_url)
+    });
     let config = Config {
         api_key,
         model_id,
         base_url,
         reasoning_enabled,
         channels_json,
+        registry_base_url,
+    };
+    let Ok(value) = serde_json::to_value(&config) else {
+        return false;
     };
-    let json = serde_json::to_string_pretty(&config).unwrap();
-    fs::write(path, json).is_ok()
+    store.set(CONFIG_KEY, value);
+    true
 }
 
 #[tauri::command]
 fn load_packs(app: AppHandle) -> Vec<RulePack> {
-    let dir = get_packs_dir(&app);
-    let mut packs = Vec::new();
-    if let Ok(entries) = fs::read_dir(&dir) {
-        for entry in entries.flatten() {
-            if entry.path().extension().map_or(false, |e| e == "json") {
-                if let Ok(json) = fs::read_to_string(entry.path()) {
-                    if let Ok(pack) = serde_json::from_str::<RulePack>(&json) {
-                        packs.push(pack);
-                    }
-                }
-            }
+    let Ok(store) = packs_store(&app) else {
+        return Vec::new();
+    };
+    // Keyed by `id`, not by store key: two store entries can deserialize to
+    // the same pack id (e.g. a legacy-migration collision), and only one
+    // should ever reach the frontend. Keep whichever is most recently
+    // updated, matching the winner `diagnose_packs` reports for the same
+    // collision.
+    let mut by_id: HashMap<String, RulePack> = HashMap::new();
+    for (key, value) in store.entries() {
+        if key.ends_with(".base") {
+            continue;
         }
+        let Ok(pack) = serde_json::from_value::<RulePack>(value) else {
+            continue;
+        };
+        by_id
+            .entry(pack.id.clone())
+            .and_modify(|existing| {
+                if pack.updated_at > existing.updated_at {
+                    *existing = pack.clone();
+                }
+            })
+            .or_insert(pack);
     }
+    let mut packs: Vec<RulePack> = by_id.into_values().collect();
     packs.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
     packs
 }
 
 #[tauri::command]
 fn save_pack(app: AppHandle, pack: RulePack) -> bool {
-    let dir = get_packs_dir(&app);
-    let path = dir.join(format!("{}.json", pack.id));
-    let json = serde_json::to_string_pretty(&pack).unwrap();
-    fs::write(path, json).is_ok()
+    let Ok(store) = packs_store(&app) else {
+        return false;
+    };
+    let Ok(value) = serde_json::to_value(&pack) else {
+        return false;
+    };
+    store.set(pack.id.clone(), value);
+    true
 }
 
 #[tauri::command]
 fn delete_pack(app: AppHandle, id: String) -> bool {
-    let dir = get_packs_dir(&app);
-    let path = dir.join(format!("{}.json", id));
-    if path.exists() {
-        fs::remove_file(path).is_ok()
-    } else {
-        false
-    }
+    let Ok(store) = packs_store(&app) else {
+        return false;
+    };
+    let existed = store.delete(&id);
+    // Drop the upgrade baseline too, so a later reinstall of this id starts
+    // a fresh three-way merge instead of diffing against a stale baseline.
+    store.delete(baseline_key(&id));
+    // And the capability grants, so a reinstalled pack can't inherit trust
+    // from the pack that used to have this id.
+    reset_pack_capabilities(&app, &id);
+    existed
 }
 
 #[tauri::command]
@@ -161,35 +684,166 @@ fn export_pack(pack: RulePack) -> String {
     serde_json::to_string_pretty(&pack).unwrap_or_default()
 }
 
+/// One finding from `diagnose_packs`, covering every way a pack can fail to
+/// show up correctly in `load_packs` without the user ever being told why.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "kind")]
+pub enum PackDiagnostic {
+    Ok {
+        key: String,
+        id: String,
+    },
+    ParseError {
+        key: String,
+        message: String,
+    },
+    /// Two store entries deserialize to the same pack id; `load_packs`'
+    /// id-keyed dedup would silently keep `winning_key` and drop the rest.
+    DuplicateId {
+        id: String,
+        winning_key: String,
+        losing_keys: Vec<String>,
+    },
+    InvalidVersion {
+        key: String,
+        id: String,
+        version: String,
+    },
+    /// Listed in `installed.json` but no pack with that id exists.
+    OrphanedInstalled {
+        id: String,
+    },
+    /// A pack exists but isn't listed in `installed.json`.
+    UninstalledPack {
+        id: String,
+    },
+}
+
+#[tauri::command]
+fn diagnose_packs(app: AppHandle) -> Vec<PackDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let Ok(store) = packs_store(&app) else {
+        return diagnostics;
+    };
+
+    let mut by_id: HashMap<String, Vec<(String, RulePack)>> = HashMap::new();
+    for (key, value) in store.entries() {
+        if key.ends_with(".base") {
+            continue;
+        }
+        match serde_json::from_value::<RulePack>(value) {
+            Ok(pack) => by_id.entry(pack.id.clone()).or_default().push((key, pack)),
+            Err(e) => diagnostics.push(PackDiagnostic::ParseError {
+                key,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    let mut known_ids = HashSet::new();
+    for (id, mut entries) in by_id {
+        known_ids.insert(id.clone());
+
+        if entries.len() > 1 {
+            entries.sort_by(|a, b| b.1.updated_at.cmp(&a.1.updated_at));
+            let winning_key = entries[0].0.clone();
+            let losing_keys = entries[1..].iter().map(|(key, _)| key.clone()).collect();
+            diagnostics.push(PackDiagnostic::DuplicateId {
+                id,
+                winning_key,
+                losing_keys,
+            });
+            continue;
+        }
+
+        let (key, pack) = entries.remove(0);
+        if parse_pack_version(&pack.version).is_err() {
+            diagnostics.push(PackDiagnostic::InvalidVersion {
+                key,
+                id,
+                version: pack.version,
+            });
+        } else {
+            diagnostics.push(PackDiagnostic::Ok { key, id });
+        }
+    }
+
+    let installed = load_installed(app.clone());
+    for id in &installed {
+        if !known_ids.contains(id) {
+            diagnostics.push(PackDiagnostic::OrphanedInstalled { id: id.clone() });
+        }
+    }
+    for id in &known_ids {
+        if !installed.contains(id) {
+            diagnostics.push(PackDiagnostic::UninstalledPack { id: id.clone() });
+        }
+    }
+
+    diagnostics
+}
+
 #[tauri::command]
-fn import_pack_json(json: String) -> Result<RulePack, String> {
-    serde_json::from_str(&json).map_err(|e| e.to_string())
+fn import_pack_json(app: AppHandle, json: String) -> Result<ImportOutcome, String> {
+    let incoming: RulePack = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    resolve_import(&app, incoming)
 }
 
 #[tauri::command]
 fn load_installed(app: AppHandle) -> Vec<String> {
-    let path = get_installed_path(&app);
-    if path.exists() {
-        let json = fs::read_to_string(&path).unwrap_or_default();
-        serde_json::from_str(&json).unwrap_or_default()
-    } else {
-        Vec::new()
-    }
+    installed_store(&app)
+        .ok()
+        .and_then(|store| store.get(INSTALLED_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
 }
 
 #[tauri::command]
 fn save_installed(app: AppHandle, ids: Vec<String>) -> bool {
-    let path = get_installed_path(&app);
-    let json = serde_json::to_string_pretty(&ids).unwrap();
-    fs::write(path, json).is_ok()
+    let Ok(store) = installed_store(&app) else {
+        return false;
+    };
+    let Ok(value) = serde_json::to_value(&ids) else {
+        return false;
+    };
+    store.set(INSTALLED_KEY, value);
+    true
+}
+
+/// Strip `system_prompt`/`rules` that depend on a permission the pack
+/// declares but hasn't been granted, returning the MemoChat-safe content.
+/// Pure so the stripping logic can be unit tested without a live store.
+fn strip_ungranted_content(pack: &RulePack, granted: &[String]) -> (String, Vec<MemoRule>) {
+    let requires = |permission: &str| pack.permissions.iter().any(|p| p == permission);
+    let is_granted = |permission: &str| granted.iter().any(|p| p == permission);
+
+    let system_prompt = if requires("prompt:override") && !is_granted("prompt:override") {
+        String::new()
+    } else {
+        pack.system_prompt.clone()
+    };
+
+    let rules = if requires("memo:write") && !is_granted("memo:write") {
+        Vec::new()
+    } else {
+        pack.rules.clone()
+    };
+
+    (system_prompt, rules)
 }
 
-/// Export a pack in MemoChat-compatible format (for importing into MemoChat)
+/// Export a pack in MemoChat-compatible format (for importing into MemoChat).
+/// Strips `system_prompt`/`rules` that depend on a permission the pack
+/// declares but the user hasn't granted, so an ungranted pack can't
+/// silently override the system prompt or rewrite memos.
 #[tauri::command]
-fn export_for_memochat(pack: RulePack) -> String {
+fn export_for_memochat(app: AppHandle, pack: RulePack) -> String {
+    let granted = granted_capabilities(&app, &pack.id);
+    let (system_prompt, rules) = strip_ungranted_content(&pack, &granted);
+
     let memochat_format = serde_json::json!({
-        "systemPrompt": pack.system_prompt,
-        "rules": pack.rules.iter().map(|r| {
+        "systemPrompt": system_prompt,
+        "rules": rules.iter().map(|r| {
             serde_json::json!({
                 "title": r.title,
                 "updateRule": r.update_rule,
@@ -199,9 +853,167 @@ fn export_for_memochat(pack: RulePack) -> String {
     serde_json::to_string_pretty(&memochat_format).unwrap_or_default()
 }
 
+/// Check requested permissions against what's been granted, denying
+/// anything outside `KNOWN_PERMISSIONS` outright regardless of what's in
+/// `granted` (a permission that's since fallen out of `KNOWN_PERMISSIONS`,
+/// or was never valid, must never be treated as granted just because a
+/// stale grant set happens to contain it). Pure so it can be unit tested
+/// without a live store.
+fn decide_capabilities(permissions: &[String], granted: &[String]) -> CapabilityDecision {
+    let mut decision = CapabilityDecision::default();
+
+    for permission in permissions {
+        if !KNOWN_PERMISSIONS.contains(&permission.as_str()) {
+            decision.denied.push(permission.clone());
+        } else if granted.contains(permission) {
+            decision.granted.push(permission.clone());
+        } else {
+            decision.requires_prompt.push(permission.clone());
+        }
+    }
+
+    decision
+}
+
+/// Check a pack's requested permissions against what the user has granted
+/// it, denying anything outside `KNOWN_PERMISSIONS` outright.
+#[tauri::command]
+fn resolve_pack_capabilities(app: AppHandle, pack: RulePack) -> CapabilityDecision {
+    let granted_set = granted_capabilities(&app, &pack.id);
+    decide_capabilities(&pack.permissions, &granted_set)
+}
+
+#[cfg(test)]
+mod capability_tests {
+    use super::*;
+
+    fn perm(s: &str) -> String {
+        s.to_string()
+    }
+
+    fn pack_with(permissions: Vec<String>) -> RulePack {
+        RulePack {
+            id: "test-pack".to_string(),
+            name: "Test Pack".to_string(),
+            description: String::new(),
+            author: String::new(),
+            version: "1.0.0".to_string(),
+            system_prompt: "do the thing".to_string(),
+            rules: vec![MemoRule {
+                title: "r".to_string(),
+                update_rule: "write memo".to_string(),
+            }],
+            memos: Vec::new(),
+            tags: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            permissions,
+        }
+    }
+
+    #[test]
+    fn unknown_permission_is_denied_even_if_present_in_a_stale_granted_set() {
+        // A permission outside KNOWN_PERMISSIONS must never be treated as
+        // granted, even if a stale grant set (e.g. from before it was
+        // removed from KNOWN_PERMISSIONS) happens to contain it.
+        let decision = decide_capabilities(
+            &[perm("totally:made-up")],
+            &[perm("totally:made-up")],
+        );
+        assert_eq!(decision.denied, vec!["totally:made-up".to_string()]);
+        assert!(decision.granted.is_empty());
+        assert!(decision.requires_prompt.is_empty());
+    }
+
+    #[test]
+    fn known_permission_requires_prompt_until_granted() {
+        let decision = decide_capabilities(&[perm("memo:write")], &[]);
+        assert!(decision.denied.is_empty());
+        assert!(decision.granted.is_empty());
+        assert_eq!(decision.requires_prompt, vec!["memo:write".to_string()]);
+    }
+
+    #[test]
+    fn known_permission_is_granted_once_in_the_granted_set() {
+        let decision = decide_capabilities(&[perm("memo:write")], &[perm("memo:write")]);
+        assert_eq!(decision.granted, vec!["memo:write".to_string()]);
+        assert!(decision.denied.is_empty());
+        assert!(decision.requires_prompt.is_empty());
+    }
+
+    #[test]
+    fn fresh_pack_permissions_default_to_ungranted() {
+        // What a freshly imported pack's permissions resolve to before the
+        // user grants anything (reset_pack_capabilities clears any stale
+        // grant for a reused id, leaving an empty granted set like this).
+        let decision = decide_capabilities(&[perm("memo:write"), perm("prompt:override")], &[]);
+        assert!(decision.granted.is_empty());
+        assert_eq!(
+            decision.requires_prompt,
+            vec!["memo:write".to_string(), "prompt:override".to_string()]
+        );
+    }
+
+    #[test]
+    fn export_strips_system_prompt_when_prompt_override_ungranted() {
+        let pack = pack_with(vec![perm("prompt:override")]);
+        let (system_prompt, rules) = strip_ungranted_content(&pack, &[]);
+        assert_eq!(system_prompt, "");
+        assert_eq!(rules.len(), 1, "rules don't depend on prompt:override");
+    }
+
+    #[test]
+    fn export_keeps_system_prompt_when_prompt_override_granted() {
+        let pack = pack_with(vec![perm("prompt:override")]);
+        let (system_prompt, _rules) =
+            strip_ungranted_content(&pack, &[perm("prompt:override")]);
+        assert_eq!(system_prompt, "do the thing");
+    }
+
+    #[test]
+    fn export_strips_rules_when_memo_write_ungranted() {
+        let pack = pack_with(vec![perm("memo:write")]);
+        let (system_prompt, rules) = strip_ungranted_content(&pack, &[]);
+        assert!(rules.is_empty());
+        assert_eq!(
+            system_prompt, "do the thing",
+            "system_prompt doesn't depend on memo:write"
+        );
+    }
+
+    #[test]
+    fn export_keeps_rules_when_memo_write_granted() {
+        let pack = pack_with(vec![perm("memo:write")]);
+        let (_system_prompt, rules) = strip_ungranted_content(&pack, &[perm("memo:write")]);
+        assert_eq!(rules.len(), 1);
+    }
+
+    #[test]
+    fn export_keeps_everything_when_pack_declares_no_permissions() {
+        let pack = pack_with(vec![]);
+        let (system_prompt, rules) = strip_ungranted_content(&pack, &[]);
+        assert_eq!(system_prompt, "do the thing");
+        assert_eq!(rules.len(), 1);
+    }
+}
+
+/// Persist the user's permission decisions for a pack (replacing any
+/// previous grant set for that id).
+#[tauri::command]
+fn set_pack_capabilities(app: AppHandle, id: String, granted: Vec<String>) -> bool {
+    let Ok(store) = capabilities_store(&app) else {
+        return false;
+    };
+    let Ok(value) = serde_json::to_value(&granted) else {
+        return false;
+    };
+    store.set(id, value);
+    true
+}
+
 /// Import from MemoChat memo-pack.json file
 #[tauri::command]
-fn import_from_memochat(app: AppHandle) -> Result<RulePack, String> {
+fn import_from_memochat(app: AppHandle) -> Result<ImportOutcome, String> {
     // Get MemoChat config directory
     let memochat_config_dir = app
         .path()
@@ -252,24 +1064,215 @@ fn import_from_memochat(app: AppHandle) -> Result<RulePack, String> {
         .unwrap_or_default();
 
     let now = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
-    Ok(RulePack {
-        id: format!("imported_{}", chrono::Local::now().timestamp_millis()),
+    let id = val["id"]
+        .as_str()
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| format!("imported_{}", chrono::Local::now().timestamp_millis()));
+    let version = val["version"]
+        .as_str()
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "1.0.0".to_string());
+
+    let incoming = RulePack {
+        id,
         name: "Imported from MemoChat".to_string(),
         description: "Current memo pack from MemoChat".to_string(),
         author: String::new(),
-        version: "1.0.0".to_string(),
+        version,
         system_prompt: String::new(),
         rules,
         memos,
         tags: vec!["imported".to_string(), "memochat".to_string()],
         created_at: now.clone(),
         updated_at: now,
-    })
+        permissions: Vec::new(),
+    };
+
+    resolve_import(&app, incoming)
+}
+
+fn configured_registry_base_url(app: &AppHandle) -> String {
+    config_store(app)
+        .ok()
+        .and_then(|store| store.get(CONFIG_KEY))
+        .and_then(|value| serde_json::from_value::<Config>(value).ok())
+        .map(|config| config.registry_base_url)
+        .unwrap_or_else(default_registry_base_url)
+}
+
+async fn fetch_registry_index(base_url: &str) -> Result<RegistryIndex, String> {
+    let url = format!("{}/index.json", base_url.trim_end_matches('/'));
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("failed to reach registry at {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("registry returned HTTP {}", response.status()));
+    }
+    response
+        .json::<RegistryIndex>()
+        .await
+        .map_err(|e| format!("failed to parse registry index: {}", e))
+}
+
+fn cached_registry_index(store: &Store<Wry>) -> Option<RegistryIndex> {
+    serde_json::from_value(store.get(REGISTRY_CACHE_KEY)?).ok()
+}
+
+/// Fetch the registry index, using a cached copy while it's within the TTL
+/// and falling back to a stale cache (so browsing still works offline) when
+/// the fetch itself fails.
+#[tauri::command]
+async fn registry_list(app: AppHandle) -> Result<Vec<RegistryEntry>, String> {
+    let store = registry_store(&app)?;
+
+    let is_fresh = store
+        .get(REGISTRY_FETCHED_AT_KEY)
+        .and_then(|v| v.as_i64())
+        .map(|fetched_at| chrono::Local::now().timestamp() - fetched_at < REGISTRY_TTL_SECONDS)
+        .unwrap_or(false);
+
+    if is_fresh {
+        if let Some(index) = cached_registry_index(&store) {
+            return Ok(index.packs);
+        }
+    }
+
+    let base_url = configured_registry_base_url(&app);
+    match fetch_registry_index(&base_url).await {
+        Ok(index) => {
+            let value = serde_json::to_value(&index).map_err(|e| e.to_string())?;
+            store.set(REGISTRY_CACHE_KEY, value);
+            store.set(
+                REGISTRY_FETCHED_AT_KEY,
+                serde_json::json!(chrono::Local::now().timestamp()),
+            );
+            Ok(index.packs)
+        }
+        Err(err) => cached_registry_index(&store)
+            .map(|index| index.packs)
+            .ok_or(err),
+    }
+}
+
+#[tauri::command]
+async fn registry_search(
+    app: AppHandle,
+    query: String,
+    tags: Vec<String>,
+) -> Result<Vec<RegistryEntry>, String> {
+    let query = query.to_lowercase();
+    let entries = registry_list(app).await?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            let matches_query = query.is_empty()
+                || entry.name.to_lowercase().contains(&query)
+                || entry.description.to_lowercase().contains(&query);
+            let matches_tags = tags.iter().all(|tag| entry.tags.contains(tag));
+            matches_query && matches_tags
+        })
+        .collect())
+}
+
+/// Download the pack referenced by `id`/`version` in the registry, verify
+/// its content hash, and install it exactly like a manual import would.
+#[tauri::command]
+async fn registry_install(
+    app: AppHandle,
+    id: String,
+    version: String,
+) -> Result<ImportOutcome, String> {
+    let entries = registry_list(app.clone()).await?;
+    let entry = entries
+        .into_iter()
+        .find(|entry| entry.id == id && entry.version == version)
+        .ok_or_else(|| format!("no registry entry for '{}' at version {}", id, version))?;
+
+    let response = reqwest::get(&entry.download_url)
+        .await
+        .map_err(|e| format!("failed to download pack '{}': {}", id, e))?;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("failed to read pack '{}' download: {}", id, e))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    let digest = format!("{:x}", hasher.finalize());
+    if digest != entry.hash {
+        return Err(format!(
+            "hash mismatch for pack '{}': expected {}, got {}",
+            id, entry.hash, digest
+        ));
+    }
+
+    let pack: RulePack = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("failed to parse downloaded pack '{}': {}", id, e))?;
+
+    // The hash only proves the bytes weren't tampered with in transit; it
+    // says nothing about whether the registry's index entry and its
+    // download_url payload actually agree with each other. registry_base_url
+    // is user-configurable, so a misconfigured or malicious registry could
+    // otherwise serve any pack under any catalog entry and we'd install it
+    // under whatever id/version is embedded in the payload instead of what
+    // the user picked.
+    if pack.id != id || pack.version != version {
+        return Err(format!(
+            "registry entry mismatch for '{}' at version {}: downloaded pack is '{}' at version {}",
+            id, version, pack.id, pack.version
+        ));
+    }
+
+    // Go through the same import resolution as a manual import: an id
+    // already installed gets the semver/three-way-merge treatment instead
+    // of a blind overwrite, and a brand new id gets its capability grants
+    // reset before anything is persisted. Return the full outcome (not just
+    // the merged pack) so an upgrade's conflicts surface to the caller the
+    // same way import_pack_json's do.
+    let outcome = resolve_import(&app, pack)?;
+    let installed_id = match &outcome {
+        ImportOutcome::New { pack } => {
+            if !save_pack(app.clone(), pack.clone()) {
+                return Err(format!("failed to save pack '{}'", id));
+            }
+            pack.id.clone()
+        }
+        ImportOutcome::Upgraded { report } => report.id.clone(),
+    };
+
+    let mut installed = load_installed(app.clone());
+    if !installed.contains(&installed_id) {
+        installed.push(installed_id);
+        save_installed(app, installed);
+    }
+
+    Ok(outcome)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .plugin(tauri_plugin_store::Builder::default().build())
+        .setup(|app| {
+            let handle = app.handle().clone();
+            // Build every store once with auto-save enabled so per-command
+            // writes debounce instead of each doing its own synchronous
+            // flush. `app.store(path)` elsewhere returns this same cached
+            // instance rather than a freshly-built default one.
+            for path in [
+                CONFIG_STORE,
+                INSTALLED_STORE,
+                PACKS_STORE,
+                REGISTRY_STORE,
+                CAPABILITIES_STORE,
+            ] {
+                StoreBuilder::new(&handle, path)
+                    .auto_save(STORE_AUTO_SAVE_DEBOUNCE)
+                    .build()?;
+            }
+            migrate_legacy_storage(&handle);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             load_config,
             save_config,
@@ -278,10 +1281,17 @@ pub fn run() {
             delete_pack,
             export_pack,
             import_pack_json,
+            upgrade_pack,
             load_installed,
             save_installed,
             export_for_memochat,
             import_from_memochat,
+            registry_list,
+            registry_search,
+            registry_install,
+            diagnose_packs,
+            resolve_pack_capabilities,
+            set_pack_capabilities,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");